@@ -0,0 +1,52 @@
+use std::collections::HashMap;
+
+/// A named set of tracks driven together as one animation, e.g. every curve
+/// that makes up a character's walk cycle.
+pub struct AnimTracks<T> {
+    tracks: HashMap<String, T>,
+}
+
+impl<T> Default for AnimTracks<T> {
+    fn default() -> Self {
+        AnimTracks {
+            tracks: HashMap::new(),
+        }
+    }
+}
+
+impl<T> AnimTracks<T> {
+    pub fn insert(&mut self, name: impl Into<String>, track: T) -> Option<T> {
+        self.tracks.insert(name.into(), track)
+    }
+
+    pub fn get(&self, name: &str) -> Option<&T> {
+        self.tracks.get(name)
+    }
+
+    pub fn get_mut(&mut self, name: &str) -> Option<&mut T> {
+        self.tracks.get_mut(name)
+    }
+
+    pub fn values(&self) -> impl Iterator<Item = &T> {
+        self.tracks.values()
+    }
+
+    pub fn values_mut(&mut self) -> impl Iterator<Item = &mut T> {
+        self.tracks.values_mut()
+    }
+}
+
+/// A [`SplineGroup`](crate::spline_group::SplineGroup) whose sample can be
+/// written back onto some other value, e.g. writing a sampled scalar curve
+/// into an entity's [`AnimatedValue`].
+pub trait Animatable: crate::spline_group::SplineGroup {
+    type Target;
+
+    fn apply(sample: &Self::Sample, target: &mut Self::Target);
+}
+
+/// A single animated scalar, driven by an [`Animator`](crate::animator::Animator)
+/// each frame via [`Animatable::apply`]. Other systems read it the same way
+/// they'd read any other component, e.g. to drive a `Transform` field.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct AnimatedValue(pub f32);
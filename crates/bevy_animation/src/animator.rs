@@ -0,0 +1,31 @@
+use crate::spline_group::SplineGroup;
+use std::ops::{Deref, DerefMut};
+
+pub use crate::spline_group::LoopStyle as AnimationLoop;
+
+/// Component that drives a single [`SplineGroup`] on an entity, advancing
+/// its playback time each frame so systems elsewhere can sample the current
+/// value out (e.g. to apply onto a `Transform`).
+pub struct Animator<T: SplineGroup> {
+    pub spline_group: T,
+}
+
+impl<T: SplineGroup> Animator<T> {
+    pub fn new(spline_group: T) -> Self {
+        Animator { spline_group }
+    }
+}
+
+impl<T: SplineGroup> Deref for Animator<T> {
+    type Target = T;
+
+    fn deref(&self) -> &T {
+        &self.spline_group
+    }
+}
+
+impl<T: SplineGroup> DerefMut for Animator<T> {
+    fn deref_mut(&mut self) -> &mut T {
+        &mut self.spline_group
+    }
+}
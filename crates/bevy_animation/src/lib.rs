@@ -1,6 +1,8 @@
 pub mod animatable;
 pub mod animator;
 pub mod plugin;
+pub mod spline_group;
+pub mod spline_groups;
 
 pub use plugin::AnimationPlugin;
 
@@ -0,0 +1,99 @@
+use crate::{
+    animatable::{AnimatedValue, Animatable},
+    animator::Animator,
+    spline_groups::one::AnimationSplineOne,
+};
+use bevy_app::prelude::{AppBuilder, Plugin};
+use bevy_core::Time;
+use bevy_ecs::{IntoQuerySystem, Query, Res};
+use bevy_tasks::ComputeTaskPool;
+
+/// Below this many entities, ticking animators serially is cheaper than the
+/// overhead of splitting the query into batches and scheduling them on the
+/// compute task pool.
+const DEFAULT_PAR_BATCH_SIZE: usize = 32;
+
+/// Adds the systems that advance every [`Animator`] each frame.
+///
+/// `par_batch_size` is the chunk size [`Query::par_for_each_mut`] splits the
+/// `Animator` query into before handing chunks to
+/// [`ComputeTaskPool`](bevy_tasks::ComputeTaskPool); entity counts at or
+/// below it are ticked serially on the calling thread instead, since
+/// `SplineGroup::advance`/`sample` are cheap enough that task-pool overhead
+/// would dominate. Scenes that never register a `ComputeTaskPool` resource
+/// also fall back to the serial path rather than panicking.
+pub struct AnimationPlugin {
+    pub par_batch_size: usize,
+}
+
+impl Default for AnimationPlugin {
+    fn default() -> Self {
+        AnimationPlugin {
+            par_batch_size: DEFAULT_PAR_BATCH_SIZE,
+        }
+    }
+}
+
+impl Plugin for AnimationPlugin {
+    fn build(&self, app: &mut AppBuilder) {
+        app.add_resource(ParBatchSize(self.par_batch_size))
+            .add_system(animate_spline_one_system.system());
+    }
+}
+
+struct ParBatchSize(usize);
+
+/// Whether ticking `entity_count` animators should go through the compute
+/// task pool, given a `batch_size` below which serial execution is cheaper.
+fn should_tick_parallel(entity_count: usize, batch_size: usize) -> bool {
+    entity_count > batch_size
+}
+
+fn animate_spline_one_system(
+    pool: Option<Res<ComputeTaskPool>>,
+    par_batch_size: Res<ParBatchSize>,
+    time: Res<Time>,
+    mut query: Query<(&mut Animator<AnimationSplineOne>, &mut AnimatedValue)>,
+) {
+    let delta_seconds = time.delta_seconds;
+    let batch_size = par_batch_size.0;
+
+    let tick = |mut animator: bevy_ecs::Mut<Animator<AnimationSplineOne>>,
+                mut value: bevy_ecs::Mut<AnimatedValue>| {
+        animator.advance(delta_seconds);
+        let sample = animator.current();
+        AnimationSplineOne::apply(&sample, &mut value);
+    };
+
+    match pool {
+        Some(pool) if should_tick_parallel(query.iter_mut().len(), batch_size) => {
+            // `SplineGroup::advance` and `sample` are pure per-entity
+            // operations with no shared state, so chunks can run fully in
+            // parallel; results are joined before `par_for_each_mut` returns.
+            query.par_for_each_mut(&pool, batch_size, |(animator, value)| {
+                tick(animator, value);
+            });
+        }
+        _ => {
+            for (animator, value) in query.iter_mut() {
+                tick(animator, value);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn stays_serial_at_or_below_batch_size() {
+        assert!(!should_tick_parallel(0, 32));
+        assert!(!should_tick_parallel(32, 32));
+    }
+
+    #[test]
+    fn goes_parallel_above_batch_size() {
+        assert!(should_tick_parallel(33, 32));
+    }
+}
@@ -1,3 +1,4 @@
+use crate::animatable::{AnimatedValue, Animatable};
 use crate::spline_group::{LoopStyle, SplineGroup};
 use splines::Spline;
 
@@ -74,3 +75,13 @@ impl SplineGroup for AnimationSplineOne {
         self.spline.sample(time)
     }
 }
+
+impl Animatable for AnimationSplineOne {
+    type Target = AnimatedValue;
+
+    fn apply(sample: &Self::Sample, target: &mut Self::Target) {
+        if let Some(value) = sample {
+            target.0 = *value;
+        }
+    }
+}
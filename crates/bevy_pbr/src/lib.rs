@@ -0,0 +1,4 @@
+mod render_graph;
+pub mod shadow;
+
+pub use shadow::ShadowPlugin;
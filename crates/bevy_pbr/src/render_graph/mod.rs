@@ -0,0 +1 @@
+pub mod pbr_pipeline;
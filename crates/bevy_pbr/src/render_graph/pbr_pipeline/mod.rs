@@ -0,0 +1,46 @@
+use crate::shadow::ShadowFilterMode;
+use bevy_render::shader::{ShaderDefines, ShaderPreprocessor};
+
+const PBR_FRAGMENT_SHADER: &str = include_str!("pbr.frag.wgsl");
+const SHADOW_SHADER_MODULE: &str = include_str!("shadow.wgsl");
+
+/// How many lights the PBR fragment shader's lighting loop is specialized
+/// for by default, until per-scene light counts drive re-specialization.
+pub const MAX_LIGHTS: u32 = 4;
+
+/// The flattened PBR fragment shader source for one pipeline specialization,
+/// produced by [`specialize_pbr_fragment_shader`].
+pub struct PbrFragmentShaderSource(pub String);
+
+/// Builds the preprocessor the PBR render graph node uses to flatten
+/// `pbr.frag.wgsl` for a given pipeline specialization, with the shared
+/// `shadow` module registered so `#import "shadow"` resolves.
+pub fn build_shader_preprocessor() -> ShaderPreprocessor {
+    let mut preprocessor = ShaderPreprocessor::new();
+    preprocessor.add_module("shadow", SHADOW_SHADER_MODULE);
+    preprocessor
+}
+
+/// The `ShaderDefines` for one PBR pipeline variant, keyed by the knobs that
+/// affect which code path the fragment shader takes: the active shadow
+/// filtering mode and how many lights are baked into the lighting loop.
+pub fn pbr_shader_defines(filter_mode: ShadowFilterMode, light_count: u32) -> ShaderDefines {
+    let mut defines = ShaderDefines::new();
+    defines.insert(
+        "SHADOW_FILTER_MODE".to_string(),
+        filter_mode.shader_define_value().to_string(),
+    );
+    defines.insert("MAX_LIGHTS".to_string(), light_count.to_string());
+    defines
+}
+
+/// Preprocesses `pbr.frag.wgsl` for the given specialization, ready to hand
+/// to wgpu's shader module creation.
+pub fn specialize_pbr_fragment_shader(
+    filter_mode: ShadowFilterMode,
+    light_count: u32,
+) -> Result<String, bevy_render::shader::PreprocessError> {
+    let preprocessor = build_shader_preprocessor();
+    let defines = pbr_shader_defines(filter_mode, light_count);
+    preprocessor.process(PBR_FRAGMENT_SHADER, &defines)
+}
@@ -0,0 +1,311 @@
+use crate::render_graph::pbr_pipeline::{
+    specialize_pbr_fragment_shader, PbrFragmentShaderSource, MAX_LIGHTS,
+};
+use bevy_app::prelude::{AppBuilder, Plugin};
+use bevy_ecs::{Added, Commands, Entity, IntoQuerySystem, Query, ResMut};
+use bevy_math::Mat4;
+use bevy_render::render_graph::{
+    nodes::{ShadowMapNode, ShadowPassNode},
+    RenderGraph,
+};
+use bevy_transform::prelude::GlobalTransform;
+
+/// How a light's shadow map is filtered when sampled in the PBR fragment
+/// shader.
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub enum ShadowFilterMode {
+    /// No shadows; the depth comparison is skipped entirely.
+    None,
+    /// A single hardware-filtered comparison sample using a comparison
+    /// sampler, giving cheap 2x2 bilinear soft edges.
+    Hardware2x2,
+    /// Percentage-Closer Filtering: a per-fragment rotated Poisson disc of
+    /// comparison taps averaged into a shadow factor.
+    Pcf,
+    /// Percentage-Closer Soft Shadows: a blocker search estimates penumbra
+    /// width from occluder distance, then runs the PCF loop with a kernel
+    /// radius scaled by that estimate, so shadows sharpen near contact and
+    /// soften with distance.
+    Pcss,
+}
+
+impl Default for ShadowFilterMode {
+    fn default() -> Self {
+        ShadowFilterMode::Pcf
+    }
+}
+
+impl ShadowFilterMode {
+    /// The `SHADOW_FILTER_MODE` value `shadow.wgsl` expects, matching the
+    /// `SHADOW_FILTER_*` constants it declares. Fed into the pipeline's
+    /// [`ShaderDefines`](bevy_render::shader::ShaderDefines) at specialization
+    /// time, so the shader branches on a compile-time constant for this mode
+    /// instead of on the runtime `ShadowLight::filter_mode`.
+    pub fn shader_define_value(self) -> &'static str {
+        match self {
+            ShadowFilterMode::None => "0",
+            ShadowFilterMode::Hardware2x2 => "1",
+            ShadowFilterMode::Pcf => "2",
+            ShadowFilterMode::Pcss => "3",
+        }
+    }
+
+    /// As [`Self::shader_define_value`], but as the `i32` `shadow.wgsl`'s
+    /// `ShadowLight::filter_mode` field expects, for the runtime uniform
+    /// buffer rather than compile-time pipeline specialization.
+    fn as_i32(self) -> i32 {
+        match self {
+            ShadowFilterMode::None => 0,
+            ShadowFilterMode::Hardware2x2 => 1,
+            ShadowFilterMode::Pcf => 2,
+            ShadowFilterMode::Pcss => 3,
+        }
+    }
+}
+
+/// The projection a light renders its shadow map with: perspective for
+/// point/spot lights, orthographic for directional lights. Drives which of
+/// [`light_space_matrix`] or [`directional_light_space_matrix`] a light's
+/// [`LightSpaceMatrix`] is computed with.
+#[derive(Debug, Clone, Copy)]
+pub enum ShadowProjection {
+    Perspective { fov: f32, near: f32, far: f32 },
+    Orthographic { half_extent: f32, near: f32, far: f32 },
+}
+
+impl Default for ShadowProjection {
+    fn default() -> Self {
+        ShadowProjection::Perspective {
+            fov: std::f32::consts::FRAC_PI_2,
+            near: 0.1,
+            far: 100.0,
+        }
+    }
+}
+
+/// Per-light configuration for shadow casting, added alongside a `Light`
+/// component to opt that light into rendering a shadow map.
+///
+/// `resolution` drives the size of the offscreen depth texture created by the
+/// light's [`ShadowMapNode`](bevy_render::render_graph::nodes::ShadowMapNode)
+/// and is read once, when that node is added; later changes to it do not
+/// resize the shadow map. `bias` and `slope_scale_bias` push sampled depth
+/// away from the surface to avoid shadow acne; `pcf_kernel_size` is the
+/// radius, in texels, of the 16-tap Poisson disc the PBR shader samples when
+/// softening the shadow edge; `filter_mode` selects which sampling strategy the
+/// shader branches into, and `light_size` is only used by `Pcss` to convert
+/// the blocker search's distance estimate into a penumbra width.
+#[derive(Debug, Clone, Copy)]
+pub struct ShadowSettings {
+    pub resolution: u32,
+    pub bias: f32,
+    pub slope_scale_bias: f32,
+    pub pcf_kernel_size: u32,
+    pub filter_mode: ShadowFilterMode,
+    pub light_size: f32,
+    pub projection: ShadowProjection,
+}
+
+impl Default for ShadowSettings {
+    fn default() -> Self {
+        ShadowSettings {
+            resolution: 1024,
+            bias: 0.005,
+            slope_scale_bias: 1.5,
+            pcf_kernel_size: 3,
+            filter_mode: ShadowFilterMode::default(),
+            light_size: 0.5,
+            projection: ShadowProjection::default(),
+        }
+    }
+}
+
+/// Computes the view-projection matrix a light renders its shadow map with,
+/// i.e. the matrix the PBR shader uses to project a fragment's world
+/// position into the light's shadow-map UV + depth space.
+///
+/// `fov` and `near`/`far` only matter for point and spot lights; directional
+/// lights should pass an orthographic projection instead via
+/// [`directional_light_space_matrix`].
+pub fn light_space_matrix(
+    light_transform: &GlobalTransform,
+    fov: f32,
+    near: f32,
+    far: f32,
+) -> Mat4 {
+    let projection = Mat4::perspective_rh(fov, 1.0, near, far);
+    let view = light_transform.compute_matrix().inverse();
+    projection * view
+}
+
+/// As [`light_space_matrix`], but for directional lights, which use an
+/// orthographic projection sized to cover the shadow-casting region instead
+/// of a perspective one.
+pub fn directional_light_space_matrix(
+    light_transform: &GlobalTransform,
+    half_extent: f32,
+    near: f32,
+    far: f32,
+) -> Mat4 {
+    let projection = Mat4::orthographic_rh(
+        -half_extent,
+        half_extent,
+        -half_extent,
+        half_extent,
+        near,
+        far,
+    );
+    let view = light_transform.compute_matrix().inverse();
+    projection * view
+}
+
+/// Dispatches to [`light_space_matrix`] or [`directional_light_space_matrix`]
+/// based on a light's [`ShadowProjection`].
+pub fn compute_light_space_matrix(
+    light_transform: &GlobalTransform,
+    projection: ShadowProjection,
+) -> Mat4 {
+    match projection {
+        ShadowProjection::Perspective { fov, near, far } => {
+            light_space_matrix(light_transform, fov, near, far)
+        }
+        ShadowProjection::Orthographic {
+            half_extent,
+            near,
+            far,
+        } => directional_light_space_matrix(light_transform, half_extent, near, far),
+    }
+}
+
+/// The light-space view-projection matrix a shadow-casting light's fragment
+/// shader sampling compares against, kept up to date by
+/// [`update_light_space_matrices_system`].
+#[derive(Debug, Clone, Copy, Default)]
+pub struct LightSpaceMatrix(pub Mat4);
+
+/// The name the [`ShadowMapNode`] for a given light entity is registered
+/// under in the [`RenderGraph`].
+pub fn shadow_map_node_name(light: Entity) -> String {
+    format!("shadow_map_{:?}", light)
+}
+
+/// The name the [`ShadowPassNode`] that draws into a given light's shadow
+/// map is registered under in the [`RenderGraph`].
+pub fn shadow_pass_node_name(light: Entity) -> String {
+    format!("shadow_pass_{:?}", light)
+}
+
+/// For every light that just gained [`ShadowSettings`]: adds a
+/// [`ShadowMapNode`] (the offscreen depth texture) and a [`ShadowPassNode`]
+/// (the pass that actually draws shadow casters into it) to the render
+/// graph, connects the two with a slot edge so the pass draws into that
+/// texture, and gives the light a [`LightSpaceMatrix`] for
+/// [`update_light_space_matrices_system`] to keep up to date.
+pub fn add_shadow_casters_system(
+    mut commands: Commands,
+    mut render_graph: ResMut<RenderGraph>,
+    query: Query<(Entity, &ShadowSettings), Added<ShadowSettings>>,
+) {
+    for (light, settings) in query.iter() {
+        let map_node_name = shadow_map_node_name(light);
+        let pass_node_name = shadow_pass_node_name(light);
+
+        render_graph.add_node(&map_node_name, ShadowMapNode::new(settings.resolution));
+        render_graph.add_node(&pass_node_name, ShadowPassNode::new());
+        render_graph
+            .add_slot_edge(
+                &map_node_name,
+                ShadowMapNode::OUT_TEXTURE,
+                &pass_node_name,
+                ShadowPassNode::IN_DEPTH_TEXTURE,
+            )
+            .expect("ShadowMapNode's texture output should match ShadowPassNode's depth input");
+
+        commands.insert_one(light, LightSpaceMatrix::default());
+    }
+}
+
+/// Recomputes every shadow-casting light's [`LightSpaceMatrix`] from its
+/// current transform and [`ShadowProjection`], so the PBR shader always
+/// samples its shadow map against an up-to-date light-space matrix.
+pub fn update_light_space_matrices_system(
+    mut query: Query<(&GlobalTransform, &ShadowSettings, &mut LightSpaceMatrix)>,
+) {
+    for (transform, settings, mut light_space_matrix) in query.iter_mut() {
+        light_space_matrix.0 = compute_light_space_matrix(transform, settings.projection);
+    }
+}
+
+/// The CPU-side mirror of `shadow.wgsl`'s `ShadowLight` struct for a single
+/// light, built by [`prepare_shadow_lights_system`].
+#[repr(C)]
+#[derive(Debug, Clone, Copy)]
+pub struct ShadowLightUniform {
+    pub view_proj: Mat4,
+    pub bias: f32,
+    pub slope_scale_bias: f32,
+    pub pcf_kernel_size: i32,
+    pub filter_mode: i32,
+    pub light_size: f32,
+}
+
+impl ShadowLightUniform {
+    fn new(light_space_matrix: &LightSpaceMatrix, settings: &ShadowSettings) -> Self {
+        ShadowLightUniform {
+            view_proj: light_space_matrix.0,
+            bias: settings.bias,
+            slope_scale_bias: settings.slope_scale_bias,
+            pcf_kernel_size: settings.pcf_kernel_size as i32,
+            filter_mode: settings.filter_mode.as_i32(),
+            light_size: settings.light_size,
+        }
+    }
+}
+
+/// Every shadow-casting light's [`ShadowLightUniform`], rebuilt each frame by
+/// [`prepare_shadow_lights_system`]. The PBR render graph node uploads this
+/// as the `shadow.wgsl` `ShadowLights` uniform buffer binding. Capped to
+/// [`MAX_LIGHTS`] entries, the array length the PBR fragment shader is
+/// specialized with; lights beyond that are left out.
+#[derive(Debug, Clone, Default)]
+pub struct ShadowLightsUniform {
+    pub lights: Vec<ShadowLightUniform>,
+}
+
+/// Rebuilds [`ShadowLightsUniform`] from every shadow-casting light's current
+/// [`LightSpaceMatrix`] and [`ShadowSettings`], the CPU side of the bridge
+/// that feeds `shadow.wgsl`'s `ShadowLight` array.
+pub fn prepare_shadow_lights_system(
+    mut shadow_lights: ResMut<ShadowLightsUniform>,
+    query: Query<(&LightSpaceMatrix, &ShadowSettings)>,
+) {
+    shadow_lights.lights.clear();
+    shadow_lights.lights.extend(
+        query
+            .iter()
+            .take(MAX_LIGHTS as usize)
+            .map(|(matrix, settings)| ShadowLightUniform::new(matrix, settings)),
+    );
+}
+
+/// Wires the shadow subsystem into the app: adds a [`ShadowMapNode`] and
+/// [`ShadowPassNode`] pair to the render graph for every shadow-casting
+/// light, keeps each one's [`LightSpaceMatrix`] up to date, rebuilds the
+/// [`ShadowLightsUniform`] the PBR pipeline binds each frame, and
+/// preprocesses the PBR fragment shader for the default filtering mode so
+/// the pipeline has a real shader module to hand to wgpu.
+pub struct ShadowPlugin;
+
+impl Plugin for ShadowPlugin {
+    fn build(&self, app: &mut AppBuilder) {
+        let fragment_shader_source =
+            specialize_pbr_fragment_shader(ShadowFilterMode::default(), MAX_LIGHTS)
+                .expect("pbr.frag.wgsl failed to preprocess");
+
+        app.add_resource(PbrFragmentShaderSource(fragment_shader_source))
+            .add_resource(ShadowLightsUniform::default())
+            .add_system(add_shadow_casters_system.system())
+            .add_system(update_light_space_matrices_system.system())
+            .add_system(prepare_shadow_lights_system.system());
+    }
+}
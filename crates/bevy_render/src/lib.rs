@@ -0,0 +1,2 @@
+pub mod render_graph;
+pub mod shader;
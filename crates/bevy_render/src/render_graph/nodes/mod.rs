@@ -0,0 +1,9 @@
+mod render_target_texture_node;
+mod shadow_map_node;
+mod shadow_pass_node;
+mod window_texture_node;
+
+pub use render_target_texture_node::{RenderTargetSizePolicy, RenderTargetTextureNode};
+pub use shadow_map_node::ShadowMapNode;
+pub use shadow_pass_node::{ShadowCaster, ShadowPassNode};
+pub use window_texture_node::WindowTextureNode;
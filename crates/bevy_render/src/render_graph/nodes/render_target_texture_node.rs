@@ -0,0 +1,182 @@
+use crate::{
+    render_graph::{Node, ResourceSlotInfo, ResourceSlots},
+    renderer::{RenderContext, RenderResourceId, RenderResourceType},
+    texture::TextureDescriptor,
+};
+use bevy_app::prelude::{EventReader, Events};
+use bevy_ecs::{Resources, World};
+use bevy_window::{WindowCreated, WindowId, WindowResized, Windows};
+use std::borrow::Cow;
+
+/// How a [`RenderTargetTextureNode`] sizes the texture it creates.
+pub enum RenderTargetSizePolicy {
+    /// Always the same size, regardless of any window.
+    Fixed { width: u32, height: u32 },
+    /// A fraction of a window's current size, e.g. a half-resolution
+    /// reflection probe or bloom ping-pong buffer.
+    WindowFraction { window_id: WindowId, scale: f32 },
+    /// Exactly a window's current size, resized whenever it is. This is
+    /// [`WindowTextureNode`](super::WindowTextureNode)'s original behavior.
+    Window(WindowId),
+}
+
+impl RenderTargetSizePolicy {
+    fn resolve(&self, windows: &Windows) -> (u32, u32) {
+        match self {
+            RenderTargetSizePolicy::Fixed { width, height } => (*width, *height),
+            RenderTargetSizePolicy::WindowFraction { window_id, scale } => {
+                let window = windows
+                    .get(*window_id)
+                    .expect("Received window resized event for non-existent window");
+                scaled_size(window.width(), window.height(), *scale)
+            }
+            RenderTargetSizePolicy::Window(window_id) => {
+                let window = windows
+                    .get(*window_id)
+                    .expect("Received window resized event for non-existent window");
+                (window.width(), window.height())
+            }
+        }
+    }
+}
+
+/// `(width, height)` scaled by `scale` and rounded down to the nearest
+/// texel, clamped to a minimum of 1 in each dimension so a small enough
+/// scale (or a 0-sized window mid-resize) never produces a zero-sized
+/// texture.
+fn scaled_size(width: u32, height: u32, scale: f32) -> (u32, u32) {
+    (
+        ((width as f32) * scale).max(1.0) as u32,
+        ((height as f32) * scale).max(1.0) as u32,
+    )
+}
+
+/// Creates and resizes an offscreen color/depth [`Texture`](crate::texture::Texture)
+/// according to a [`RenderTargetSizePolicy`], centralizing the
+/// create/remove-on-resize logic that [`WindowTextureNode`](super::WindowTextureNode)
+/// and [`ShadowMapNode`](super::ShadowMapNode) both need, instead of each
+/// reimplementing it against their own size policy.
+pub struct RenderTargetTextureNode {
+    size_policy: RenderTargetSizePolicy,
+    descriptor: TextureDescriptor,
+    window_created_event_reader: EventReader<WindowCreated>,
+    window_resized_event_reader: EventReader<WindowResized>,
+    texture_resource: Option<RenderResourceId>,
+}
+
+impl RenderTargetTextureNode {
+    pub const OUT_TEXTURE: &'static str = "texture";
+
+    pub fn new(size_policy: RenderTargetSizePolicy, descriptor: TextureDescriptor) -> Self {
+        RenderTargetTextureNode {
+            size_policy,
+            descriptor,
+            window_created_event_reader: Default::default(),
+            window_resized_event_reader: Default::default(),
+            texture_resource: None,
+        }
+    }
+
+    /// Updates a [`RenderTargetSizePolicy::Fixed`] target's dimensions; the
+    /// texture is recreated the next time the node updates. No-op for any
+    /// other policy.
+    pub fn set_fixed_size(&mut self, width: u32, height: u32) {
+        if let RenderTargetSizePolicy::Fixed { width: w, height: h } = &mut self.size_policy {
+            *w = width;
+            *h = height;
+        }
+    }
+
+    fn should_update(&mut self, resources: &Resources) -> bool {
+        match &self.size_policy {
+            RenderTargetSizePolicy::Fixed { width, height } => {
+                self.texture_resource.is_none()
+                    || self.descriptor.size.width != *width
+                    || self.descriptor.size.height != *height
+            }
+            RenderTargetSizePolicy::WindowFraction { window_id, .. }
+            | RenderTargetSizePolicy::Window(window_id) => {
+                let window_id = *window_id;
+                let window_created_events = resources.get::<Events<WindowCreated>>().unwrap();
+                let window_resized_events = resources.get::<Events<WindowResized>>().unwrap();
+
+                self.texture_resource.is_none()
+                    || self
+                        .window_created_event_reader
+                        .find_latest(&window_created_events, |e| e.id == window_id)
+                        .is_some()
+                    || self
+                        .window_resized_event_reader
+                        .find_latest(&window_resized_events, |e| e.id == window_id)
+                        .is_some()
+            }
+        }
+    }
+}
+
+impl Node for RenderTargetTextureNode {
+    fn output(&self) -> &[ResourceSlotInfo] {
+        static OUTPUT: &[ResourceSlotInfo] = &[ResourceSlotInfo {
+            name: Cow::Borrowed(RenderTargetTextureNode::OUT_TEXTURE),
+            resource_type: RenderResourceType::Texture,
+        }];
+        OUTPUT
+    }
+
+    fn update(
+        &mut self,
+        _world: &World,
+        resources: &Resources,
+        render_context: &mut dyn RenderContext,
+        _input: &ResourceSlots,
+        output: &mut ResourceSlots,
+    ) {
+        const TEXTURE: usize = 0;
+
+        if !self.should_update(resources) {
+            return;
+        }
+
+        // `Fixed` ignores `windows` entirely, so don't require the `Windows`
+        // resource to be registered at all for a fixed-size target like
+        // `ShadowMapNode` — fetching it unconditionally would panic any
+        // context that hasn't set one up yet.
+        let (width, height) = match &self.size_policy {
+            RenderTargetSizePolicy::Fixed { width, height } => (*width, *height),
+            RenderTargetSizePolicy::WindowFraction { .. } | RenderTargetSizePolicy::Window(_) => {
+                let windows = resources.get::<Windows>().unwrap();
+                self.size_policy.resolve(&windows)
+            }
+        };
+
+        let render_resource_context = render_context.resources_mut();
+        if let Some(RenderResourceId::Texture(old_texture)) = self.texture_resource {
+            render_resource_context.remove_texture(old_texture);
+        }
+
+        self.descriptor.size.width = width;
+        self.descriptor.size.height = height;
+
+        let texture_resource = render_resource_context.create_texture(self.descriptor);
+        let resource_id = RenderResourceId::Texture(texture_resource);
+        self.texture_resource = Some(resource_id);
+        output.set(TEXTURE, resource_id);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn scaled_size_rounds_down() {
+        assert_eq!(scaled_size(1280, 720, 0.5), (640, 360));
+        assert_eq!(scaled_size(101, 101, 0.5), (50, 50));
+    }
+
+    #[test]
+    fn scaled_size_never_zero() {
+        assert_eq!(scaled_size(1, 1, 0.01), (1, 1));
+        assert_eq!(scaled_size(0, 0, 1.0), (1, 1));
+    }
+}
@@ -0,0 +1,65 @@
+use super::{RenderTargetSizePolicy, RenderTargetTextureNode};
+use crate::{
+    render_graph::{Node, ResourceSlotInfo, ResourceSlots},
+    renderer::RenderContext,
+    texture::{Extent3d, TextureDescriptor, TextureFormat},
+};
+use bevy_ecs::{Resources, World};
+
+/// Creates the offscreen depth [`Texture`](crate::texture::Texture) that a
+/// light's [`ShadowPassNode`](super::ShadowPassNode) draws scene depth into,
+/// for later sampling by the PBR lighting shader's shadow comparison.
+///
+/// A thin wrapper around a [`RenderTargetTextureNode`] using
+/// [`RenderTargetSizePolicy::Fixed`], so it shares the same
+/// create/remove-on-resize logic as [`WindowTextureNode`](super::WindowTextureNode)
+/// instead of reimplementing it.
+pub struct ShadowMapNode {
+    inner: RenderTargetTextureNode,
+}
+
+impl ShadowMapNode {
+    pub const OUT_TEXTURE: &'static str = RenderTargetTextureNode::OUT_TEXTURE;
+
+    pub fn new(resolution: u32) -> Self {
+        let descriptor = TextureDescriptor {
+            size: Extent3d::new(resolution, resolution, 1),
+            format: TextureFormat::Depth32Float,
+            ..Default::default()
+        };
+
+        ShadowMapNode {
+            inner: RenderTargetTextureNode::new(
+                RenderTargetSizePolicy::Fixed {
+                    width: resolution,
+                    height: resolution,
+                },
+                descriptor,
+            ),
+        }
+    }
+
+    /// Resizes the shadow map to `resolution` x `resolution` the next time the
+    /// node updates.
+    pub fn set_resolution(&mut self, resolution: u32) {
+        self.inner.set_fixed_size(resolution, resolution);
+    }
+}
+
+impl Node for ShadowMapNode {
+    fn output(&self) -> &[ResourceSlotInfo] {
+        self.inner.output()
+    }
+
+    fn update(
+        &mut self,
+        world: &World,
+        resources: &Resources,
+        render_context: &mut dyn RenderContext,
+        input: &ResourceSlots,
+        output: &mut ResourceSlots,
+    ) {
+        self.inner
+            .update(world, resources, render_context, input, output);
+    }
+}
@@ -0,0 +1,95 @@
+use crate::{
+    draw::Draw,
+    pass::{
+        LoadOp, Operations, PassDescriptor, RenderPassDepthStencilAttachmentDescriptor,
+        TextureAttachment,
+    },
+    pipeline::RenderPipelines,
+    render_graph::{Node, ResourceSlotInfo, ResourceSlots},
+    renderer::{RenderContext, RenderResourceType},
+};
+use bevy_ecs::{Resources, World};
+use std::borrow::Cow;
+
+/// Marker for an entity that should be drawn into every light's shadow map
+/// while its [`Draw`] is visible, in addition to whatever color pass draws it.
+pub struct ShadowCaster;
+
+/// Draws every visible [`ShadowCaster`]'s [`Draw`] render commands, with that
+/// entity's own [`RenderPipelines`], into the depth texture fed in on
+/// [`Self::IN_DEPTH_TEXTURE`]. This node binds no light-space view-projection
+/// matrix of its own, so the depth written is projected by whatever the
+/// casters' pipelines and bindings use. Connected to its light's
+/// [`ShadowMapNode`](super::ShadowMapNode) via a slot edge from
+/// [`ShadowMapNode::OUT_TEXTURE`](super::ShadowMapNode::OUT_TEXTURE).
+pub struct ShadowPassNode {
+    descriptor: PassDescriptor,
+}
+
+impl ShadowPassNode {
+    pub const IN_DEPTH_TEXTURE: &'static str = "depth_texture";
+
+    pub fn new() -> Self {
+        ShadowPassNode {
+            descriptor: PassDescriptor {
+                color_attachments: Vec::new(),
+                depth_stencil_attachment: Some(RenderPassDepthStencilAttachmentDescriptor {
+                    attachment: TextureAttachment::Input(Self::IN_DEPTH_TEXTURE.to_string()),
+                    depth_ops: Some(Operations {
+                        load: LoadOp::Clear(1.0),
+                        store: true,
+                    }),
+                    stencil_ops: None,
+                }),
+                sample_count: 1,
+            },
+        }
+    }
+}
+
+impl Default for ShadowPassNode {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Node for ShadowPassNode {
+    fn input(&self) -> &[ResourceSlotInfo] {
+        static INPUT: &[ResourceSlotInfo] = &[ResourceSlotInfo {
+            name: Cow::Borrowed(ShadowPassNode::IN_DEPTH_TEXTURE),
+            resource_type: RenderResourceType::Texture,
+        }];
+        INPUT
+    }
+
+    fn update(
+        &mut self,
+        world: &World,
+        resources: &Resources,
+        render_context: &mut dyn RenderContext,
+        input: &ResourceSlots,
+        _output: &mut ResourceSlots,
+    ) {
+        let depth_texture = input
+            .get(Self::IN_DEPTH_TEXTURE)
+            .expect("ShadowPassNode's depth texture input is not connected");
+
+        if let Some(depth_stencil_attachment) = &mut self.descriptor.depth_stencil_attachment {
+            depth_stencil_attachment.attachment = TextureAttachment::Id(depth_texture);
+        }
+
+        render_context.begin_pass(&self.descriptor, resources, &mut |render_pass| {
+            for (_caster, draw, pipelines) in
+                world.query::<(&ShadowCaster, &Draw, &RenderPipelines)>().iter()
+            {
+                if !draw.is_visible {
+                    continue;
+                }
+
+                for render_command in draw.render_commands() {
+                    render_command.apply(render_pass, pipelines);
+                }
+            }
+        });
+    }
+}
@@ -0,0 +1,380 @@
+use std::collections::{HashMap, HashSet};
+use std::fmt;
+
+/// The set of active `#define`s a shader is preprocessed with, keyed by
+/// name. Pipeline specialization (shadow filtering mode, active light
+/// count, ...) builds one of these per variant and feeds it to
+/// [`ShaderPreprocessor::process`] to produce the flattened WGSL for that
+/// variant.
+pub type ShaderDefines = HashMap<String, String>;
+
+#[derive(Debug)]
+pub enum PreprocessError {
+    /// `#import`/`#include` named a module that was never registered via
+    /// [`ShaderPreprocessor::add_module`].
+    ModuleNotFound(String),
+    /// A module (transitively) imports itself.
+    CyclicImport(Vec<String>),
+    /// An `#else`/`#endif` appeared without a matching `#ifdef`/`#ifndef`,
+    /// or an `#ifdef`/`#ifndef` was never closed.
+    UnmatchedConditional(String),
+    /// An `#ifdef`/`#ifndef`/`#import`/`#include`/`#define` was missing its
+    /// argument.
+    MalformedDirective(String),
+}
+
+impl fmt::Display for PreprocessError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            PreprocessError::ModuleNotFound(name) => {
+                write!(f, "shader module '{}' is not registered", name)
+            }
+            PreprocessError::CyclicImport(chain) => {
+                write!(f, "cyclic shader import: {}", chain.join(" -> "))
+            }
+            PreprocessError::UnmatchedConditional(line) => {
+                write!(f, "unmatched #else/#endif: {}", line)
+            }
+            PreprocessError::MalformedDirective(line) => {
+                write!(f, "malformed preprocessor directive: {}", line)
+            }
+        }
+    }
+}
+
+impl std::error::Error for PreprocessError {}
+
+/// Runs over shader source before it is handed to wgpu, resolving
+/// `#import`/`#include` against a registered module map, substituting
+/// `#define`d tokens, and stripping `#ifdef`/`#ifndef`/`#else`/`#endif`
+/// blocks based on the active define set.
+///
+/// This lets shader authors share lighting/shadow helper code across shaders
+/// (`#import "shadow"`) instead of hand-duplicating it, and lets pipeline
+/// specialization select code paths with defines instead of branching in the
+/// hot loop.
+#[derive(Default)]
+pub struct ShaderPreprocessor {
+    modules: HashMap<String, String>,
+}
+
+impl ShaderPreprocessor {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Registers `source` under `name` so it can be pulled in elsewhere via
+    /// `#import "name"` or `#include "name"`.
+    pub fn add_module(&mut self, name: impl Into<String>, source: impl Into<String>) {
+        self.modules.insert(name.into(), source.into());
+    }
+
+    /// Resolves imports, applies `defines`, and returns the flattened WGSL.
+    pub fn process(
+        &self,
+        source: &str,
+        defines: &ShaderDefines,
+    ) -> Result<String, PreprocessError> {
+        let mut defines = defines.clone();
+        let mut import_stack = Vec::new();
+        let mut emitted = HashSet::new();
+        self.resolve(source, &mut defines, &mut import_stack, &mut emitted)
+    }
+
+    /// Walks `source` once, resolving `#ifdef`/`#ifndef`/`#else`/`#endif`
+    /// and `#import`/`#include` together so an import's dedup bookkeeping
+    /// only ever sees occurrences that actually survive conditional
+    /// stripping. Resolving imports as a separate pass ahead of conditional
+    /// stripping would mark a module "seen" the first time it's imported
+    /// even if that occurrence sits in a branch this variant drops, and
+    /// silently skip a later, active import of the same module instead of
+    /// pasting it in.
+    fn resolve(
+        &self,
+        source: &str,
+        defines: &mut ShaderDefines,
+        import_stack: &mut Vec<String>,
+        emitted: &mut HashSet<String>,
+    ) -> Result<String, PreprocessError> {
+        let mut output = String::with_capacity(source.len());
+
+        // Whether each currently-open `#ifdef`/`#ifndef` block (and its
+        // ancestors) is currently emitting lines, plus whether that block has
+        // already taken its `#ifdef`/`#else` branch.
+        let mut block_active = vec![true];
+        let mut block_taken = vec![true];
+
+        for line in source.lines() {
+            let trimmed = line.trim_start();
+
+            if matches!(
+                trimmed.trim_end(),
+                "#ifdef" | "#ifndef" | "#import" | "#include" | "#define"
+            ) {
+                return Err(PreprocessError::MalformedDirective(line.to_string()));
+            }
+
+            if let Some(rest) = trimmed.strip_prefix("#define ") {
+                if parent_active(&block_active) {
+                    let mut parts = rest.splitn(2, char::is_whitespace);
+                    let name = parts
+                        .next()
+                        .filter(|s| !s.is_empty())
+                        .ok_or_else(|| PreprocessError::MalformedDirective(line.to_string()))?;
+                    let value = parts.next().unwrap_or("").trim().to_string();
+                    defines.insert(name.to_string(), value);
+                }
+                continue;
+            }
+
+            if let Some(rest) = trimmed.strip_prefix("#ifdef ") {
+                let name = rest.trim();
+                if name.is_empty() {
+                    return Err(PreprocessError::MalformedDirective(line.to_string()));
+                }
+                let condition = defines.contains_key(name) && parent_active(&block_active);
+                block_active.push(condition);
+                block_taken.push(condition);
+                continue;
+            }
+
+            if let Some(rest) = trimmed.strip_prefix("#ifndef ") {
+                let name = rest.trim();
+                if name.is_empty() {
+                    return Err(PreprocessError::MalformedDirective(line.to_string()));
+                }
+                let condition = !defines.contains_key(name) && parent_active(&block_active);
+                block_active.push(condition);
+                block_taken.push(condition);
+                continue;
+            }
+
+            if trimmed.trim_end() == "#else" {
+                // Only the root entry is left: no `#ifdef`/`#ifndef` is open.
+                if block_taken.len() <= 1 {
+                    return Err(PreprocessError::UnmatchedConditional(line.to_string()));
+                }
+                let taken = block_taken
+                    .pop()
+                    .ok_or_else(|| PreprocessError::UnmatchedConditional(line.to_string()))?;
+                block_active.pop();
+                let active = !taken && parent_active(&block_active);
+                block_active.push(active);
+                block_taken.push(taken || active);
+                continue;
+            }
+
+            if trimmed.trim_end() == "#endif" {
+                block_active
+                    .pop()
+                    .ok_or_else(|| PreprocessError::UnmatchedConditional(line.to_string()))?;
+                block_taken
+                    .pop()
+                    .ok_or_else(|| PreprocessError::UnmatchedConditional(line.to_string()))?;
+                continue;
+            }
+
+            if !parent_active(&block_active) {
+                continue;
+            }
+
+            if let Some(name) = parse_import_directive(trimmed)? {
+                if import_stack.contains(&name) {
+                    let mut chain = import_stack.clone();
+                    chain.push(name);
+                    return Err(PreprocessError::CyclicImport(chain));
+                }
+
+                // Already pasted in earlier in this shader (e.g. two
+                // modules that both `#import "shadow"`): skip it instead of
+                // emitting duplicate declarations.
+                if emitted.contains(&name) {
+                    continue;
+                }
+
+                let module_source = self
+                    .modules
+                    .get(&name)
+                    .ok_or_else(|| PreprocessError::ModuleNotFound(name.clone()))?;
+
+                import_stack.push(name.clone());
+                let resolved_module = self.resolve(module_source, defines, import_stack, emitted)?;
+                import_stack.pop();
+                emitted.insert(name);
+
+                output.push_str(&resolved_module);
+                output.push('\n');
+                continue;
+            }
+
+            output.push_str(&substitute_defines(line, defines));
+            output.push('\n');
+        }
+
+        if block_active.len() != 1 {
+            return Err(PreprocessError::UnmatchedConditional(
+                "missing #endif".to_string(),
+            ));
+        }
+
+        Ok(output)
+    }
+}
+
+fn parent_active(block_active: &[bool]) -> bool {
+    *block_active.last().unwrap_or(&true)
+}
+
+fn parse_import_directive(trimmed: &str) -> Result<Option<String>, PreprocessError> {
+    for prefix in ["#import ", "#include "] {
+        if let Some(rest) = trimmed.strip_prefix(prefix) {
+            let name = rest.trim().trim_matches('"');
+            if name.is_empty() {
+                return Err(PreprocessError::MalformedDirective(trimmed.to_string()));
+            }
+            return Ok(Some(name.to_string()));
+        }
+    }
+    Ok(None)
+}
+
+fn substitute_defines(line: &str, defines: &ShaderDefines) -> String {
+    if defines.is_empty() {
+        return line.to_string();
+    }
+
+    let mut result = String::with_capacity(line.len());
+    let mut rest = line;
+
+    'outer: while !rest.is_empty() {
+        for (name, value) in defines.iter() {
+            if let Some(after) = rest.strip_prefix(name.as_str()) {
+                let boundary_ok = after
+                    .chars()
+                    .next()
+                    .map(|c| !c.is_alphanumeric() && c != '_')
+                    .unwrap_or(true);
+                let preceded_ok = result
+                    .chars()
+                    .last()
+                    .map(|c| !c.is_alphanumeric() && c != '_')
+                    .unwrap_or(true);
+                if boundary_ok && preceded_ok {
+                    result.push_str(value);
+                    rest = after;
+                    continue 'outer;
+                }
+            }
+        }
+
+        let mut chars = rest.chars();
+        if let Some(c) = chars.next() {
+            result.push(c);
+        }
+        rest = chars.as_str();
+    }
+
+    result
+}
+
+/// Tracks which module names are reachable from a root module, used by
+/// callers that want to validate a module map up-front rather than
+/// discovering a missing import mid-specialization.
+pub fn reachable_modules(
+    preprocessor: &ShaderPreprocessor,
+    root: &str,
+) -> Result<HashSet<String>, PreprocessError> {
+    let mut seen = HashSet::new();
+    let mut stack = vec![root.to_string()];
+
+    while let Some(name) = stack.pop() {
+        if !seen.insert(name.clone()) {
+            continue;
+        }
+
+        let source = preprocessor
+            .modules
+            .get(&name)
+            .ok_or_else(|| PreprocessError::ModuleNotFound(name.clone()))?;
+
+        for line in source.lines() {
+            if let Some(import) = parse_import_directive(line.trim_start())? {
+                stack.push(import);
+            }
+        }
+    }
+
+    Ok(seen)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn diamond_import_is_only_emitted_once() {
+        let mut preprocessor = ShaderPreprocessor::new();
+        preprocessor.add_module("shadow", "struct ShadowLight {};");
+        preprocessor.add_module("lighting", "#import \"shadow\"\nfn light() {}");
+
+        let source = "#import \"shadow\"\n#import \"lighting\"\nfn main() {}";
+        let result = preprocessor.process(source, &ShaderDefines::new()).unwrap();
+
+        assert_eq!(result.matches("struct ShadowLight").count(), 1);
+        assert!(result.contains("fn light() {}"));
+        assert!(result.contains("fn main() {}"));
+    }
+
+    #[test]
+    fn cyclic_import_is_rejected() {
+        let mut preprocessor = ShaderPreprocessor::new();
+        preprocessor.add_module("a", "#import \"b\"");
+        preprocessor.add_module("b", "#import \"a\"");
+
+        let result = preprocessor.process("#import \"a\"", &ShaderDefines::new());
+        assert!(matches!(result, Err(PreprocessError::CyclicImport(_))));
+    }
+
+    #[test]
+    fn ifdef_else_endif_strip_inactive_branch() {
+        let preprocessor = ShaderPreprocessor::new();
+        let source = "#ifdef FOO\nkept\n#else\ndropped\n#endif\n";
+
+        let mut defines = ShaderDefines::new();
+        defines.insert("FOO".to_string(), String::new());
+        let result = preprocessor.process(source, &defines).unwrap();
+        assert_eq!(result.trim(), "kept");
+
+        let result = preprocessor.process(source, &ShaderDefines::new()).unwrap();
+        assert_eq!(result.trim(), "dropped");
+    }
+
+    #[test]
+    fn import_inactive_in_one_branch_still_emits_when_active_in_another() {
+        let mut preprocessor = ShaderPreprocessor::new();
+        preprocessor.add_module("shadow", "struct ShadowLight {};");
+
+        // The first `#import "shadow"` sits behind an `#ifdef` this variant
+        // doesn't define, so it must not count as "already emitted" and
+        // swallow the second, active import.
+        let source =
+            "#ifdef UNUSED\n#import \"shadow\"\n#endif\n#import \"shadow\"\nfn main() {}";
+        let result = preprocessor.process(source, &ShaderDefines::new()).unwrap();
+
+        assert_eq!(result.matches("struct ShadowLight").count(), 1);
+        assert!(result.contains("fn main() {}"));
+    }
+
+    #[test]
+    fn define_tokens_are_substituted() {
+        let preprocessor = ShaderPreprocessor::new();
+        let mut defines = ShaderDefines::new();
+        defines.insert("MAX_LIGHTS".to_string(), "4".to_string());
+
+        let result = preprocessor
+            .process("let lights: array<Light, MAX_LIGHTS>;", &defines)
+            .unwrap();
+
+        assert_eq!(result.trim(), "let lights: array<Light, 4>;");
+    }
+}
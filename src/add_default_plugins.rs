@@ -17,6 +17,7 @@ impl AddDefaultPlugins for AppBuilder {
         self.add_plugin(bevy_render::RenderPlugin::default());
         self.add_plugin(bevy_sprite::SpritePlugin::default());
         self.add_plugin(bevy_pbr::PbrPlugin::default());
+        self.add_plugin(bevy_pbr::ShadowPlugin);
         self.add_plugin(bevy_ui::UiPlugin::default());
         self.add_plugin(bevy_text::TextPlugin::default());
         self.add_plugin(bevy_animation::AnimationPlugin::default());